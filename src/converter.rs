@@ -1,26 +1,52 @@
 use half::f16;
-use numpy::PyReadonlyArray2;
+use numpy::ndarray::Array2;
+use numpy::{IntoPyArray, PyArray2, PyReadonlyArray2};
 use pyo3::exceptions::{PyValueError, PyKeyError};
 use pyo3::prelude::*;
 use rayon::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// ## Converter can convert sparse arrays to JSON / Pseudo String format efficiently
-/// 
+///
 /// ### Args:
 ///     vocab_dict (Option<HashMap<i32, String>>): A map of `token_id -> token_str`
-/// 
+///     allowed_token_ids (Option<HashSet<i32>>): If given, only these token ids survive
+///                             quantization. Useful when projecting a model's full vocab head
+///                             onto a restricted index vocabulary.
+///     allowed_token_ids_file (Option<&str>): Path to a newline-delimited file of allowed token
+///                             ids, unioned on top of `allowed_token_ids`.
+///
 #[pyclass(name = "Converter")]
 pub struct PyConverter {
     vocab_dict: HashMap<i32, String>,
+    allowed_token_ids: Option<HashSet<i32>>,
 }
 
 #[pymethods]
 impl PyConverter {
     #[new]
-    #[pyo3(signature = (vocab_dict=HashMap::new()))]
-    pub fn new(vocab_dict: HashMap<i32, String>) -> Self {
-        Self { vocab_dict }
+    #[pyo3(signature = (vocab_dict=HashMap::new(), allowed_token_ids=None, allowed_token_ids_file=None))]
+    pub fn new(
+        vocab_dict: HashMap<i32, String>,
+        allowed_token_ids: Option<HashSet<i32>>,
+        allowed_token_ids_file: Option<&str>,
+    ) -> PyResult<Self> {
+        // Union the explicit set with any ids loaded from the resource file.
+        let allowed_token_ids = match (allowed_token_ids, allowed_token_ids_file) {
+            (base, Some(path)) => {
+                let mut set = base.unwrap_or_default();
+                for line in load_lines_from_file(path).map_err(PyValueError::new_err)? {
+                    let id = line.parse::<i32>().map_err(|_| {
+                        PyValueError::new_err(format!("Invalid token id `{}` in {}", line, path))
+                    })?;
+                    set.insert(id);
+                }
+                Some(set)
+            }
+            (base, None) => base,
+        };
+
+        Ok(Self { vocab_dict, allowed_token_ids })
     }
 
     /// Same as `self.convert_sparse_reps_to_json_f32`
@@ -82,6 +108,12 @@ impl PyConverter {
                     let mut sparse_rep: HashMap<String, i32> = HashMap::new();
 
                     for (vocab_id, &value) in reps.row(batch_id).indexed_iter() {
+                        // Skip dimensions outside the restricted index vocabulary.
+                        if let Some(allowed) = &self.allowed_token_ids {
+                            if !allowed.contains(&(vocab_id as i32)) {
+                                continue;
+                            }
+                        }
                         // Value is not zero
                         // Large margin (1e-4) is used here, because the reps may be casted from
                         // other reps with lower precision
@@ -163,6 +195,12 @@ impl PyConverter {
                     let mut sparse_rep: HashMap<String, i32> = HashMap::new();
 
                     for (vocab_id, &value) in reps.row(batch_id).indexed_iter() {
+                        // Skip dimensions outside the restricted index vocabulary.
+                        if let Some(allowed) = &self.allowed_token_ids {
+                            if !allowed.contains(&(vocab_id as i32)) {
+                                continue;
+                            }
+                        }
                         if value > f16::ZERO || value < f16::NEG_ZERO { // Value is not zero
                             if value < f16::NEG_ZERO && !allow_negative_values {
                                 continue;
@@ -320,4 +358,417 @@ impl PyConverter {
                 .collect::<Vec<String>>() // Collect into Vec<String>
         })
     }
+
+    /// Same as `self.convert_sparse_reps_to_binary_f32`
+    /// A float32 multi-threaded version of Convert sparse representations to the compact
+    /// varint/delta binary format. Returns one `bytes` buffer per row.
+    ///
+    /// ### Args:
+    ///     reps (PyReadonlyArray2<f32>): Numpy f32 array, shape [batch_size, vocab_dim]
+    ///     quantization_factor (i32): Upscaling factor. Quantized reps = (reps * quantization_factor).floor()
+    ///     allow_negative_values (bool): Whether to preserve negative values.
+    #[pyo3(signature = (reps, quantization_factor=100, allow_negative_values=false))]
+    pub fn convert_sparse_reps_to_binary(
+        &self,
+        py: Python,
+        reps: PyReadonlyArray2<f32>,
+        quantization_factor: i32,
+        allow_negative_values: bool,
+    ) -> PyResult<Vec<Vec<u8>>> {
+        self.convert_sparse_reps_to_binary_f32(py, reps, quantization_factor, allow_negative_values)
+    }
+
+    /// A float32 multi-threaded version of Convert sparse representations to a compact binary format.
+    ///
+    /// Each row is encoded as, in order:
+    ///     * a LEB128 unsigned varint `count` of entries;
+    ///     * the entries sorted ascending by `token_id`, the first `token_id` as a varint and each
+    ///       subsequent one as a varint *delta* from the previous (monotonic, so deltas are positive);
+    ///     * each quantized weight as a zigzag-encoded varint (so negative weights survive when
+    ///       `allow_negative_values`).
+    /// An empty row emits a single `count = 0`. This is far smaller and faster to parse for
+    /// inverted-index ingestion than JSON with decimal-string keys.
+    ///
+    /// ### Args:
+    ///     reps (PyReadonlyArray2<f32>): Numpy f32 array, shape [batch_size, vocab_dim]
+    ///     quantization_factor (i32): Upscaling factor. Quantized reps = (reps * quantization_factor).floor()
+    ///     allow_negative_values (bool): Whether to preserve negative values.
+    #[pyo3(signature = (reps, quantization_factor=100, allow_negative_values=false))]
+    pub fn convert_sparse_reps_to_binary_f32(
+        &self,
+        py: Python,
+        reps: PyReadonlyArray2<f32>,
+        quantization_factor: i32,
+        allow_negative_values: bool,
+    ) -> PyResult<Vec<Vec<u8>>> {
+        let reps = reps.as_array();
+
+        if reps.shape().len() != 2 {
+            return Err(PyValueError::new_err("Input numpy array must be 2-dimensional."));
+        }
+
+        let result = py.allow_threads(|| {
+            let quant_factor = quantization_factor as f32;
+
+            (0..reps.shape()[0])
+                .into_par_iter()
+                .map(|batch_id| {
+                    let mut pairs: Vec<(i32, i32)> = Vec::new();
+
+                    for (vocab_id, &value) in reps.row(batch_id).indexed_iter() {
+                        // Skip dimensions outside the restricted index vocabulary.
+                        if let Some(allowed) = &self.allowed_token_ids {
+                            if !allowed.contains(&(vocab_id as i32)) {
+                                continue;
+                            }
+                        }
+                        if value > 1e-4 || value < -1e-4 {
+                            if value < -1e-4 && !allow_negative_values {
+                                continue;
+                            }
+
+                            let quantized_value = (value * quant_factor).floor() as i32;
+
+                            if quantized_value > 0 {
+                                pairs.push((vocab_id as i32, quantized_value));
+                            } else if quantized_value < 0 && allow_negative_values {
+                                pairs.push((vocab_id as i32, quantized_value));
+                            }
+                        }
+                    }
+
+                    encode_binary_row(&mut pairs)
+                })
+                .collect::<Vec<Vec<u8>>>()
+        });
+
+        Ok(result)
+    }
+
+    /// A float16 multi-threaded version of Convert sparse representations to the compact binary format.
+    /// See `self.convert_sparse_reps_to_binary_f32` for the layout.
+    ///
+    /// ### Args:
+    ///     reps (PyReadonlyArray2<f16>): Numpy f16 array, shape [batch_size, vocab_dim]
+    ///     quantization_factor (i32): Upscaling factor. Quantized reps = (reps * quantization_factor).floor()
+    ///     allow_negative_values (bool): Whether to preserve negative values.
+    #[pyo3(signature = (reps, quantization_factor=100, allow_negative_values=false))]
+    pub fn convert_sparse_reps_to_binary_f16(
+        &self,
+        py: Python,
+        reps: PyReadonlyArray2<f16>,
+        quantization_factor: i32,
+        allow_negative_values: bool,
+    ) -> PyResult<Vec<Vec<u8>>> {
+        let reps = reps.as_array();
+
+        if reps.shape().len() != 2 {
+            return Err(PyValueError::new_err("Input numpy array must be 2-dimensional."));
+        }
+
+        let result = py.allow_threads(|| {
+            let quant_factor = quantization_factor as f32;
+
+            (0..reps.shape()[0])
+                .into_par_iter()
+                .map(|batch_id| {
+                    let mut pairs: Vec<(i32, i32)> = Vec::new();
+
+                    for (vocab_id, &value) in reps.row(batch_id).indexed_iter() {
+                        // Skip dimensions outside the restricted index vocabulary.
+                        if let Some(allowed) = &self.allowed_token_ids {
+                            if !allowed.contains(&(vocab_id as i32)) {
+                                continue;
+                            }
+                        }
+                        if value > f16::ZERO || value < f16::NEG_ZERO {
+                            if value < f16::NEG_ZERO && !allow_negative_values {
+                                continue;
+                            }
+
+                            let value = value.to_f32();
+                            let quantized_value = (value * quant_factor).floor() as i32;
+
+                            if quantized_value > 0 {
+                                pairs.push((vocab_id as i32, quantized_value));
+                            } else if quantized_value < 0 && allow_negative_values {
+                                pairs.push((vocab_id as i32, quantized_value));
+                            }
+                        }
+                    }
+
+                    encode_binary_row(&mut pairs)
+                })
+                .collect::<Vec<Vec<u8>>>()
+        });
+
+        Ok(result)
+    }
+
+    /// Decode the compact binary format produced by `convert_sparse_reps_to_binary_*` back into
+    /// `(token_id, quantized_weight)` pairs, one list per row. This is the symmetric reader that
+    /// makes the binary format round-trip.
+    ///
+    /// ### Args:
+    ///     binary_reps (Vec<Vec<u8>>): One buffer per row, as returned by the binary encoders.
+    pub fn convert_binary_to_sparse_reps(
+        &self,
+        py: Python,
+        binary_reps: Vec<Vec<u8>>,
+    ) -> PyResult<Vec<Vec<(i32, i32)>>> {
+        py.allow_threads(|| {
+            binary_reps
+                .into_par_iter()
+                .map(|buf| decode_binary_row(&buf).map_err(PyValueError::new_err))
+                .collect::<PyResult<Vec<Vec<(i32, i32)>>>>()
+        })
+    }
+
+    /// Invert `convert_json_reps_to_pseudo_text`: split each pseudo-text row on whitespace
+    /// and count token repetitions back into a `{token: freq}` map.
+    ///
+    /// ### Args:
+    ///     pseudo_texts (Vec<String>): Pseudo-text rows, each `token token ... token`.
+    pub fn convert_pseudo_text_to_json(
+        &self,
+        py: Python,
+        pseudo_texts: Vec<String>,
+    ) -> Vec<HashMap<String, i32>> {
+        py.allow_threads(|| {
+            pseudo_texts
+                .into_par_iter()
+                .map(|text| {
+                    let mut dict_rep: HashMap<String, i32> = HashMap::new();
+                    for token in text.split_whitespace() {
+                        *dict_rep.entry(token.to_string()).or_insert(0) += 1;
+                    }
+                    dict_rep
+                })
+                .collect::<Vec<HashMap<String, i32>>>()
+        })
+    }
+
+    /// Rebuild a dense `[batch, vocab_dim]` f32 array from JSON sparse reps, dividing each
+    /// frequency by `quantization_factor` to undo the quantization upscaling.
+    ///
+    /// String keys are resolved to column indices either via the supplied inverse `token_to_id`
+    /// map, or (when it is `None`) lazily from `self.vocab_dict` if it was populated, falling back
+    /// to parsing the key as a decimal `token_id`. The `negative_prefix` convention is honoured to
+    /// restore negative weights. Keys that resolve to an out-of-range or unknown column (such as the
+    /// `-1` padding sentinel) are skipped. The `[PAD]` sentinel is skipped only when `[PAD]` is not
+    /// in the inverse map; otherwise it is written to that token's column like any other key.
+    ///
+    /// ### Args:
+    ///     json_reps (Vec<HashMap<String, i32>>): Format `{token_id / token: int frequency}`.
+    ///     vocab_dim (usize): Number of columns of the reconstructed dense array.
+    ///     quantization_factor (i32): Upscaling factor used when the reps were quantized.
+    ///     token_to_id (Option<HashMap<String, i32>>): Inverse `token -> id` map. Built from
+    ///                             `self.vocab_dict` when `None` and `vocab_dict` is non-empty.
+    ///     negative_prefix (&str): Prefix marking negative-weight entries.
+    #[pyo3(signature = (json_reps, vocab_dim, quantization_factor=100, token_to_id=None, negative_prefix="neg_"))]
+    pub fn convert_json_reps_to_dense<'py>(
+        &self,
+        py: Python<'py>,
+        json_reps: Vec<HashMap<String, i32>>,
+        vocab_dim: usize,
+        quantization_factor: i32,
+        token_to_id: Option<HashMap<String, i32>>,
+        negative_prefix: &str,
+    ) -> Bound<'py, PyArray2<f32>> {
+        // Resolve the inverse vocabulary map once: caller-supplied, else lazily inverted
+        // from `self.vocab_dict`, else empty (decimal-string keys are parsed directly).
+        let token_to_id: HashMap<String, i32> = token_to_id.unwrap_or_else(|| {
+            self.vocab_dict
+                .iter()
+                .map(|(id, token)| (token.clone(), *id))
+                .collect()
+        });
+
+        let batch_size = json_reps.len();
+        let quant_factor = quantization_factor as f32;
+
+        let mut dense = Array2::<f32>::zeros((batch_size, vocab_dim));
+        for (batch_id, dict_rep) in json_reps.iter().enumerate() {
+            for (key, &freq) in dict_rep.iter() {
+                // Strip the negative prefix to restore the sign.
+                let (token, sign) = if key.starts_with(negative_prefix) {
+                    (&key[negative_prefix.len()..], -1.0_f32)
+                } else {
+                    (key.as_str(), 1.0_f32)
+                };
+
+                // Map the token back to a column index.
+                let col = match token_to_id.get(token) {
+                    Some(&id) => id,
+                    None => match token.parse::<i32>() {
+                        Ok(id) => id,
+                        Err(_) => continue, // Unknown key (e.g. `[PAD]`), skip.
+                    },
+                };
+
+                if col < 0 || col as usize >= vocab_dim {
+                    continue; // Out-of-range (e.g. the `-1` padding sentinel), skip.
+                }
+
+                dense[[batch_id, col as usize]] = sign * (freq as f32) / quant_factor;
+            }
+        }
+
+        dense.into_pyarray_bound(py)
+    }
+
+    /// Decode the compact binary format into JSON sparse reps, the symmetric counterpart of
+    /// `convert_sparse_reps_to_json`. Token ids are optionally mapped to token strings via
+    /// `self.vocab_dict`, and negative weights are re-encoded with `negative_prefix`.
+    ///
+    /// ### Args:
+    ///     binary_reps (Vec<Vec<u8>>): One buffer per row, as returned by the binary encoders.
+    ///     convert_id_to_token (bool): True - Return token str; False - Return token_id str
+    ///     negative_prefix (&str): Prefix applied to negative-weight entries.
+    #[pyo3(signature = (binary_reps, convert_id_to_token=false, negative_prefix="neg_"))]
+    pub fn convert_binary_to_json(
+        &self,
+        py: Python,
+        binary_reps: Vec<Vec<u8>>,
+        convert_id_to_token: bool,
+        negative_prefix: &str,
+    ) -> PyResult<Vec<HashMap<String, i32>>> {
+        py.allow_threads(|| {
+            binary_reps
+                .into_par_iter()
+                .map(|buf| {
+                    let pairs = decode_binary_row(&buf).map_err(PyValueError::new_err)?;
+
+                    let mut sparse_rep: HashMap<String, i32> = HashMap::new();
+                    for (token_id, weight) in pairs {
+                        let token_key = if convert_id_to_token {
+                            self.vocab_dict
+                                .get(&token_id)
+                                .ok_or_else(|| PyErr::new::<PyKeyError, _>(
+                                    format!("Token id {} not found in vocab_dict.", token_id)))?
+                                .clone()
+                        } else {
+                            token_id.to_string()
+                        };
+
+                        if weight > 0 {
+                            sparse_rep.insert(token_key, weight);
+                        } else if weight < 0 {
+                            sparse_rep.insert(negative_prefix.to_string() + &token_key, -weight);
+                        }
+                    }
+
+                    if sparse_rep.is_empty() {
+                        let pad_key = if convert_id_to_token {
+                            "[PAD]".to_string()
+                        } else {
+                            "-1".to_string()
+                        };
+                        sparse_rep.insert(pad_key, 1);
+                    }
+
+                    Ok(sparse_rep)
+                })
+                .collect::<PyResult<Vec<HashMap<String, i32>>>>()
+        })
+    }
+}
+
+/// Append `value` to `buf` as an unsigned LEB128 varint.
+fn write_uvarint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value != 0 {
+            byte |= 0x80;
+        }
+        buf.push(byte);
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Read an unsigned LEB128 varint from `buf` starting at `*pos`, advancing `*pos` past it.
+fn read_uvarint(buf: &[u8], pos: &mut usize) -> Result<u64, String> {
+    let mut result: u64 = 0;
+    let mut shift: u32 = 0;
+    loop {
+        if *pos >= buf.len() {
+            return Err("Unexpected end of buffer while reading varint.".to_string());
+        }
+        let byte = buf[*pos];
+        *pos += 1;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+    }
+    Ok(result)
+}
+
+/// Map a signed integer to an unsigned one so that small magnitudes stay small (zigzag).
+fn zigzag_encode(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Inverse of [`zigzag_encode`].
+fn zigzag_decode(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Encode one row of `(token_id, weight)` pairs into the compact varint/delta binary format.
+/// The pairs are sorted ascending by `token_id` in place so that deltas are monotonic.
+fn encode_binary_row(pairs: &mut Vec<(i32, i32)>) -> Vec<u8> {
+    pairs.sort_by_key(|&(token_id, _)| token_id);
+
+    let mut buf: Vec<u8> = Vec::new();
+    write_uvarint(&mut buf, pairs.len() as u64);
+
+    let mut prev: i64 = 0;
+    for (i, &(token_id, weight)) in pairs.iter().enumerate() {
+        let token_id = token_id as i64;
+        if i == 0 {
+            write_uvarint(&mut buf, token_id as u64); // first token_id, absolute
+        } else {
+            write_uvarint(&mut buf, (token_id - prev) as u64); // positive delta
+        }
+        prev = token_id;
+        write_uvarint(&mut buf, zigzag_encode(weight as i64));
+    }
+
+    buf
+}
+
+/// Decode one row of the compact binary format back into `(token_id, weight)` pairs.
+fn decode_binary_row(buf: &[u8]) -> Result<Vec<(i32, i32)>, String> {
+    let mut pos: usize = 0;
+    let count = read_uvarint(buf, &mut pos)? as usize;
+
+    let mut pairs: Vec<(i32, i32)> = Vec::with_capacity(count);
+    let mut prev: i64 = 0;
+    for i in 0..count {
+        let token_id = if i == 0 {
+            read_uvarint(buf, &mut pos)? as i64
+        } else {
+            prev + read_uvarint(buf, &mut pos)? as i64
+        };
+        prev = token_id;
+        let weight = zigzag_decode(read_uvarint(buf, &mut pos)?);
+        pairs.push((token_id as i32, weight as i32));
+    }
+
+    Ok(pairs)
+}
+
+/// Load a newline-delimited resource file into a list of non-empty, trimmed lines.
+fn load_lines_from_file(path: &str) -> Result<Vec<String>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read `{}`: {}", path, e))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
 }
\ No newline at end of file
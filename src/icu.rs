@@ -1,10 +1,30 @@
 use pyo3::prelude::*;
 use pyo3::exceptions::*;
-use icu_segmenter::WordSegmenter;
+use icu_segmenter::{SentenceSegmenter, WordSegmenter};
 use itertools::Itertools;
 use onig::Regex;
+use rust_stemmers::Stemmer;
 // use rayon::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use crate::regex_tokenizer::build_stemmer;
+
+/// Full-width CJK sentence terminators that the explicit fallback splitter breaks on.
+const CJK_TERMINATORS: &[char] = &['。', '！', '？', '；', '：'];
+
+/// Closing quote / parenthesis marks absorbed into the preceding sentence when they trail a
+/// terminator.
+const CJK_TRAILING_MARKS: &[char] = &['」', '』', '）', '”', '’', '"'];
+
+/// Resolve a language code to its compiled-in, newline-delimited stopword list.
+fn bundled_stopwords(lang: &str) -> Option<&'static str> {
+    match lang.to_lowercase().as_str() {
+        "en" | "english" => Some(include_str!("stopwords/en.txt")),
+        "fr" | "french" => Some(include_str!("stopwords/fr.txt")),
+        "de" | "german" => Some(include_str!("stopwords/de.txt")),
+        "zh" | "chinese" => Some(include_str!("stopwords/zh.txt")),
+        _ => None,
+    }
+}
 
 /// PyO3 wrapper of ICUWordPreTokenizer.
 /// 
@@ -18,49 +38,85 @@ pub struct PyICUWordPreTokenizer {
 
 #[pymethods]
 impl PyICUWordPreTokenizer {
+    /// Init func
+    ///
+    /// ### Args:
+    ///     stopword_sets (HashSet<String>): User-supplied stopwords, unioned on top of any bundled lists.
+    ///     stopword_langs (Vec<String>): Language codes (e.g. "en", "zh", "de", "fr") whose bundled,
+    ///                             compiled-in stopword lists are loaded. Multiple codes are unioned for
+    ///                             mixed-language corpora.
+    ///     stemmer_lang (Option<&str>): Snowball stemmer language code, or `None` / `"no-stem"` to disable.
     #[new]
-    #[pyo3(signature = (stopword_sets = HashSet::new()))]
-    pub fn new(stopword_sets: HashSet<String>) -> PyResult<Self> {
-        let tokenizer = ICUWordPreTokenizer::new(stopword_sets).map_err(|err| PyValueError::new_err(err))?;
+    #[pyo3(signature = (stopword_sets = HashSet::new(), stopword_langs = Vec::new(), stemmer_lang = None))]
+    pub fn new(
+        stopword_sets: HashSet<String>,
+        stopword_langs: Vec<String>,
+        stemmer_lang: Option<&str>,
+    ) -> PyResult<Self> {
+        let tokenizer = ICUWordPreTokenizer::new(stopword_sets, stopword_langs, stemmer_lang)
+                                        .map_err(|err| PyValueError::new_err(err))?;
 
         Ok(Self {tokenizer})
     }
 
-    #[pyo3(signature = (text, remove_stopwords = false, lowercase = true))]
+    #[pyo3(signature = (text, remove_stopwords = false, lowercase = true, fold_diacritics = false))]
     pub fn tokenize(
         &self,
         py: Python,
         text: String,
         remove_stopwords: bool,
         lowercase: bool,
+        fold_diacritics: bool,
     ) -> Vec<String> {
         py.allow_threads(|| {
-            self.tokenizer.tokenize(text, remove_stopwords, lowercase)
+            self.tokenizer.tokenize(text, remove_stopwords, lowercase, fold_diacritics)
         })
     }
 
-    #[pyo3(signature = (texts, remove_stopwords = false, lowercase = true))]
+    #[pyo3(signature = (texts, remove_stopwords = false, lowercase = true, fold_diacritics = false))]
     pub fn batch_tokenize(
         &self,
         py: Python,
         texts: Vec<String>,
         remove_stopwords: bool,
         lowercase: bool,
+        fold_diacritics: bool,
     ) -> Vec<Vec<String>> {
         py.allow_threads(|| {
-            self.tokenizer.batch_tokenize(texts, remove_stopwords, lowercase)
+            self.tokenizer.batch_tokenize(texts, remove_stopwords, lowercase, fold_diacritics)
         })
     }
 
-    #[pyo3(signature = (texts, remove_stopwords = false, lowercase = true))]
+    /// Tokenize `text` and pair each token with its start/end byte offsets into the ORIGINAL
+    /// (pre-clean, pre-lowercase) string, so reported spans index the caller's untouched input.
+    ///
+    /// ### Args:
+    ///     text (String): String text.
+    ///     remove_stopwords (bool): Whether to remove stopwords defined in `self.stopword_sets`. Default `false`.
+    ///     lowercase (bool): Whether to lowercase the returned tokens. Offsets are unaffected. Default `true`.
+    #[pyo3(signature = (text, remove_stopwords = false, lowercase = true))]
+    pub fn tokenize_with_offsets(
+        &self,
+        py: Python,
+        text: String,
+        remove_stopwords: bool,
+        lowercase: bool,
+    ) -> Vec<(String, usize, usize)> {
+        py.allow_threads(|| {
+            self.tokenizer.tokenize_with_offsets(&text, remove_stopwords, lowercase)
+        })
+    }
+
+    #[pyo3(signature = (texts, remove_stopwords = false, lowercase = true, fold_diacritics = false))]
     pub fn __call__(
         &self,
         py: Python,
         texts: Vec<String>,
         remove_stopwords: bool,
         lowercase: bool,
+        fold_diacritics: bool,
     ) -> Vec<Vec<String>> {
-        self.batch_tokenize(py, texts, remove_stopwords, lowercase)
+        self.batch_tokenize(py, texts, remove_stopwords, lowercase, fold_diacritics)
     }
 }
 
@@ -73,24 +129,46 @@ pub struct ICUWordPreTokenizer {
     word_segmenter: WordSegmenter,
     re_bad_chars: Regex,
     stopword_sets: HashSet<String>,
+    stemmer: Option<Stemmer>,
 }
 
 #[allow(unused)]
 impl ICUWordPreTokenizer {
     /// Init func
-    /// 
+    ///
     /// ### Args:
-    ///     
-    ///      stopword_sets (HashSet<String>): Set of stopwords str.
-    pub fn new(stopword_sets: HashSet<String>) -> Result<Self, String> {
+    ///
+    ///      stopword_sets (HashSet<String>): Set of stopwords str, unioned on top of the bundled lists.
+    ///      stopword_langs (Vec<String>): Language codes whose bundled stopword lists are loaded.
+    ///      stemmer_lang (Option<&str>): Snowball stemmer language code (e.g. "en"), or `None` /
+    ///                             `"no-stem"` to disable stemming.
+    pub fn new(
+        mut stopword_sets: HashSet<String>,
+        stopword_langs: Vec<String>,
+        stemmer_lang: Option<&str>,
+    ) -> Result<Self, String> {
         let word_segmenter = WordSegmenter::new_auto();
         let re_bad_chars = Regex::new(r"[\p{Cc}\p{Cs}\p{Cn}]+")
             .map_err(|e| e.description().to_string())?;
+        let stemmer = build_stemmer(stemmer_lang)?;
+
+        // Union any bundled, compiled-in stopword lists requested by language code.
+        for lang in &stopword_langs {
+            match bundled_stopwords(lang) {
+                Some(list) => stopword_sets.extend(
+                    list.lines()
+                        .map(|line| line.trim().to_string())
+                        .filter(|line| !line.is_empty()),
+                ),
+                None => return Err(format!("No bundled stopword list for language `{}`", lang)),
+            }
+        }
 
         Ok(Self {
             word_segmenter,
             re_bad_chars,
             stopword_sets,
+            stemmer,
         })
     }
 
@@ -106,11 +184,14 @@ impl ICUWordPreTokenizer {
     ///     text (String): String text.
     ///     remove_stopwords (bool): Whether to remove stopwords defined in `self.stopword_sets`. Default `false`.
     ///     lowercase (bool): Whether to lowercase the inputs. Default `true`.
+    ///     fold_diacritics (bool): Whether to chain an ASCII-folded transliteration after each
+    ///                             non-CJK word whose folded form differs. Default `false`.
     pub fn tokenize(
         &self,
         text: String,
         remove_stopwords: bool,
         lowercase: bool,
+        fold_diacritics: bool,
     ) -> Vec<String> {
         // Remove invalid characters and trim
         let mut clean_text = self.re_bad_chars.replace_all(text.as_str(), "")
@@ -132,12 +213,18 @@ impl ICUWordPreTokenizer {
         for (start, end) in self.word_segmenter.segment_str(&clean_text).tuple_windows().into_iter() {
             let word = clean_text[start..end].trim();
             if !word.is_empty() {
-                if remove_stopwords {
-                    if !self.stopword_sets.contains(word) {
-                        words.push(word.to_string());
-                    }
+                if remove_stopwords && self.stopword_sets.contains(word) {
+                    continue;
+                }
+                // Reduce to the stem (after stopword removal) when a stemmer is configured.
+                let word = match &self.stemmer {
+                    Some(stemmer) => stemmer.stem(word).into_owned(),
+                    None => word.to_string(),
+                };
+                if fold_diacritics {
+                    crate::regex_tokenizer::push_with_optional_fold(&mut words, word);
                 } else {
-                    words.push(word.to_string());
+                    words.push(word);
                 }
             }
         }
@@ -157,17 +244,231 @@ impl ICUWordPreTokenizer {
     ///     texts (Vec<String>): List of texts.
     ///     remove_stopwords (bool): Whether to remove stopwords defined in `self.stopword_sets`. Default `false`.
     ///     lowercase (bool): Whether to lowercase the inputs. Default `true`.
+    ///     fold_diacritics (bool): Whether to chain an ASCII-folded transliteration after each
+    ///                             non-CJK word whose folded form differs. Default `false`.
     pub fn batch_tokenize(
         &self,
         texts: Vec<String>,
         remove_stopwords: bool,
         lowercase: bool,
+        fold_diacritics: bool,
     ) -> Vec<Vec<String>> {
         texts
             // .into_par_iter()     # This causes hang when using Python Multi-processing
             .into_iter()
-            .map(|text| self.tokenize(text, remove_stopwords, lowercase))
+            .map(|text| self.tokenize(text, remove_stopwords, lowercase, fold_diacritics))
             .collect()
     }
+
+    /// Tokenize `text`, returning each token together with its `[start, end)` byte offsets into the
+    /// ORIGINAL text (before control-character stripping and lowercasing).
+    ///
+    /// To keep the reported spans anchored to the caller's untouched string, the control-character
+    /// removal is done by hand while recording a translation table `clean_byte -> original_byte`,
+    /// and segmentation runs on the (non-lowercased) cleaned text so byte positions stay aligned.
+    /// The token string itself is lowercased on the way out when `lowercase` is set.
+    ///
+    /// ### Args:
+    ///     text (String): String text.
+    ///     remove_stopwords (bool): Whether to remove stopwords defined in `self.stopword_sets`.
+    ///     lowercase (bool): Whether to lowercase the returned tokens. Offsets are unaffected.
+    pub fn tokenize_with_offsets(
+        &self,
+        text: &str,
+        remove_stopwords: bool,
+        lowercase: bool,
+    ) -> Vec<(String, usize, usize)> {
+        // Build the cleaned text together with a map from each cleaned byte to the original byte
+        // index it came from. Control-character removal only drops whole matches, so retained bytes
+        // are byte-identical to the original.
+        let mut clean = String::new();
+        let mut map: Vec<usize> = Vec::new();
+        let mut last = 0;
+        for (start, end) in self.re_bad_chars.find_iter(text) {
+            for (off, ch) in text[last..start].char_indices() {
+                let orig_idx = last + off;
+                clean.push(ch);
+                for _ in 0..ch.len_utf8() {
+                    map.push(orig_idx);
+                }
+            }
+            last = end;
+        }
+        for (off, ch) in text[last..].char_indices() {
+            let orig_idx = last + off;
+            clean.push(ch);
+            for _ in 0..ch.len_utf8() {
+                map.push(orig_idx);
+            }
+        }
+        // Sentinel so a token ending at the final cleaned byte can look up its end offset.
+        map.push(text.len());
+
+        if clean.is_empty() {
+            return vec![];
+        }
+
+        // Account for the `.trim()` the plain tokenizer applies to the cleaned text.
+        let lead = clean.len() - clean.trim_start().len();
+        let end_b = clean.trim_end().len();
+        let seg_text = &clean[lead..end_b];
+        if seg_text.is_empty() {
+            return vec![];
+        }
+
+        let mut out: Vec<(String, usize, usize)> = Vec::new();
+        for (start, end) in self.word_segmenter.segment_str(seg_text).tuple_windows().into_iter() {
+            let raw = &seg_text[start..end];
+            let leading_ws = raw.len() - raw.trim_start().len();
+            let word = raw.trim();
+            if word.is_empty() {
+                continue;
+            }
+
+            // `lead + start + leading_ws` is the cleaned-byte index of the first word byte.
+            let clean_idx = lead + start + leading_ws;
+            let orig_start = map[clean_idx];
+            // Translate the end through `map` too: when a control char was stripped from inside the
+            // token's original extent, the end is not `orig_start + word.len()` bytes away.
+            let orig_end = map[clean_idx + word.len()];
+
+            let token = if lowercase { word.to_lowercase() } else { word.to_string() };
+            if remove_stopwords && self.stopword_sets.contains(token.as_str()) {
+                continue;
+            }
+
+            out.push((token, orig_start, orig_end));
+        }
+
+        out
+    }
 }
 
+
+
+/// PyO3 wrapper of CJKSentenceSegmenter.
+///
+/// `PySentenceSegmenter` splits text into sentences using [ICU4X](https://github.com/unicode-org/icu4x)'s
+/// sentence segmenter, combined with an explicit fallback splitter on full-width CJK terminators
+/// (。！？；：) and their paired closing quote/paren marks. This dual strategy keeps mixed
+/// Chinese/English passage chunking consistent with the word segmenter in this module.
+#[pyclass(name = "SentenceSegmenter")]
+pub struct PySentenceSegmenter {
+    segmenter: CJKSentenceSegmenter,
+}
+
+#[pymethods]
+impl PySentenceSegmenter {
+    #[new]
+    pub fn new() -> PyResult<Self> {
+        let segmenter = CJKSentenceSegmenter::new();
+        Ok(Self { segmenter })
+    }
+
+    /// Split `text` into a list of sentences.
+    pub fn split(
+        &self,
+        py: Python,
+        text: String,
+    ) -> Vec<String> {
+        py.allow_threads(|| self.segmenter.split(&text))
+    }
+
+    /// Batch variant (runs sequentially; see note on `into_par_iter` below): for each input,
+    /// return its sentences paired with their `[start, end)` byte offsets into that input.
+    pub fn batch_split(
+        &self,
+        py: Python,
+        texts: Vec<String>,
+    ) -> Vec<Vec<(String, usize, usize)>> {
+        py.allow_threads(|| {
+            texts
+                // .into_par_iter()     # This causes hang when using Python Multi-processing
+                .into_iter()
+                .map(|text| self.segmenter.segment_spans(&text))
+                .collect()
+        })
+    }
+
+    /// Split `text` and pair each sentence with its `[start, end)` byte offsets into `text`.
+    pub fn split_with_spans(
+        &self,
+        py: Python,
+        text: String,
+    ) -> Vec<(String, usize, usize)> {
+        py.allow_threads(|| self.segmenter.segment_spans(&text))
+    }
+}
+
+
+/// `CJKSentenceSegmenter` wraps ICU4X's sentence segmenter and augments it with a fallback
+/// splitter on full-width CJK terminators, so mixed Chinese/English text is chunked correctly.
+#[allow(unused)]
+pub struct CJKSentenceSegmenter {
+    sentence_segmenter: SentenceSegmenter,
+}
+
+#[allow(unused)]
+impl CJKSentenceSegmenter {
+    pub fn new() -> Self {
+        let sentence_segmenter = SentenceSegmenter::new();
+        Self { sentence_segmenter }
+    }
+
+    /// Split `text` into sentences, discarding the offsets.
+    pub fn split(&self, text: &str) -> Vec<String> {
+        self.segment_spans(text)
+            .into_iter()
+            .map(|(sentence, _, _)| sentence)
+            .collect()
+    }
+
+    /// Split `text` into sentences, each paired with its `[start, end)` byte offsets into `text`.
+    ///
+    /// The ICU sentence boundaries are unioned with extra breakpoints placed right after every
+    /// full-width CJK terminator (absorbing any trailing closing quote/paren marks), then the text
+    /// is cut at the sorted union of boundaries.
+    pub fn segment_spans(&self, text: &str) -> Vec<(String, usize, usize)> {
+        if text.is_empty() {
+            return vec![];
+        }
+
+        // ICU sentence boundaries, plus the document endpoints.
+        let mut breaks: BTreeSet<usize> = self.sentence_segmenter.segment_str(text).collect();
+        breaks.insert(0);
+        breaks.insert(text.len());
+
+        // Fallback: break after each CJK terminator and any trailing closing marks.
+        for (idx, ch) in text.char_indices() {
+            if CJK_TERMINATORS.contains(&ch) {
+                let mut end = idx + ch.len_utf8();
+                for next in text[end..].chars() {
+                    if CJK_TRAILING_MARKS.contains(&next) {
+                        end += next.len_utf8();
+                    } else {
+                        break;
+                    }
+                }
+                breaks.insert(end);
+            }
+        }
+
+        // Cut the text at consecutive boundaries, trimming whitespace and keeping offsets honest.
+        let boundaries: Vec<usize> = breaks.into_iter().collect();
+        let mut out: Vec<(String, usize, usize)> = Vec::new();
+        for window in boundaries.windows(2) {
+            let (start, end) = (window[0], window[1]);
+            let slice = &text[start..end];
+            let leading = slice.len() - slice.trim_start().len();
+            let trimmed = slice.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let real_start = start + leading;
+            let real_end = real_start + trimmed.len();
+            out.push((trimmed.to_string(), real_start, real_end));
+        }
+
+        out
+    }
+}
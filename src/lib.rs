@@ -2,6 +2,7 @@ use pyo3::prelude::*;
 mod converter;
 mod icu;
 mod regex_tokenizer;
+mod ngram_tokenizer;
 mod qa_annotator;
 
 /// Expose the classes to Python.
@@ -11,5 +12,7 @@ fn sparse_emb_util(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_class::<regex_tokenizer::PyRegexTokenizer>()?;
     m.add_class::<qa_annotator::PyQAAnnotator>()?;
     m.add_class::<icu::PyICUWordPreTokenizer>()?;
+    m.add_class::<icu::PySentenceSegmenter>()?;
+    m.add_class::<ngram_tokenizer::PyNgramTokenizer>()?;
     Ok(())
 }
@@ -0,0 +1,215 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// PyO3 wrapper of NgramTokenizer.
+///
+/// `NgramTokenizer` emits overlapping character n-grams of configurable min/max length, mirroring
+/// cozo's `NgramTokenizer`. For CJK input such as "搜索引擎" with n=2 it yields
+/// "搜索", "索引", "引擎"; for Latin text it operates on whole words (or the whole string). This
+/// fills the gap where ICU word segmentation is too coarse for scripts without reliable word
+/// boundaries and gives downstream sparse encoders subword-robust term overlap.
+#[pyclass(name = "NgramTokenizer")]
+pub struct PyNgramTokenizer {
+    pub inner: NgramTokenizer,
+}
+
+#[pymethods]
+impl PyNgramTokenizer {
+    /// Init func
+    ///
+    /// ### Args:
+    ///     min_gram (usize): Minimum n-gram length. Default `2`.
+    ///     max_gram (usize): Maximum n-gram length. Default `2`.
+    ///     edge_only (bool): Emit only prefix n-grams (anchored at the start of each unit), for
+    ///                       autocomplete-style sparse features. Default `false`.
+    ///     split_on_whitespace (bool): Generate n-grams per whitespace-delimited word (`true`) or
+    ///                       over the whole trimmed string (`false`). Default `true`.
+    ///     prefix_marker (Option<String>): Boundary marker prepended to each unit before
+    ///                       n-gramming (e.g. "^"). Default `None`.
+    ///     suffix_marker (Option<String>): Boundary marker appended to each unit before
+    ///                       n-gramming (e.g. "$"). Default `None`.
+    #[new]
+    #[pyo3(signature = (
+        min_gram = 2,
+        max_gram = 2,
+        edge_only = false,
+        split_on_whitespace = true,
+        prefix_marker = None,
+        suffix_marker = None
+    ))]
+    pub fn new(
+        min_gram: usize,
+        max_gram: usize,
+        edge_only: bool,
+        split_on_whitespace: bool,
+        prefix_marker: Option<String>,
+        suffix_marker: Option<String>,
+    ) -> PyResult<Self> {
+        let inner = NgramTokenizer::new(min_gram, max_gram, edge_only, split_on_whitespace, prefix_marker, suffix_marker)
+                                        .map_err(|err| PyValueError::new_err(err))?;
+        Ok(Self { inner })
+    }
+
+    pub fn tokenize(
+        &self,
+        py: Python,
+        text: String,
+    ) -> Vec<String> {
+        py.allow_threads(|| self.inner.tokenize(&text))
+    }
+
+    pub fn batch_tokenize(
+        &self,
+        py: Python,
+        texts: Vec<String>,
+    ) -> Vec<Vec<String>> {
+        py.allow_threads(|| {
+            texts.into_par_iter()
+                 .map(|text| self.inner.tokenize(&text))
+                 .collect()
+        })
+    }
+
+    pub fn __call__(
+        &self,
+        py: Python,
+        texts: Vec<String>,
+    ) -> Vec<Vec<String>> {
+        self.batch_tokenize(py, texts)
+    }
+}
+
+/// `NgramTokenizer` emits overlapping character n-grams, optionally anchored at the unit start
+/// (`edge_only`) and wrapped in boundary markers.
+pub struct NgramTokenizer {
+    min_gram: usize,
+    max_gram: usize,
+    edge_only: bool,
+    split_on_whitespace: bool,
+    prefix_marker: Option<String>,
+    suffix_marker: Option<String>,
+}
+
+#[allow(unused)]
+impl NgramTokenizer {
+    /// Args:
+    ///     min_gram (usize): Minimum n-gram length, must be `>= 1`.
+    ///     max_gram (usize): Maximum n-gram length, must be `>= min_gram`.
+    ///     edge_only (bool): Emit only prefix n-grams anchored at the unit start.
+    ///     split_on_whitespace (bool): N-gram per word (`true`) or over the whole string (`false`).
+    ///     prefix_marker (Option<String>): Boundary marker prepended to each unit.
+    ///     suffix_marker (Option<String>): Boundary marker appended to each unit.
+    pub fn new(
+        min_gram: usize,
+        max_gram: usize,
+        edge_only: bool,
+        split_on_whitespace: bool,
+        prefix_marker: Option<String>,
+        suffix_marker: Option<String>,
+    ) -> Result<Self, String> {
+        if min_gram < 1 {
+            return Err("min_gram must be >= 1".to_string());
+        }
+        if max_gram < min_gram {
+            return Err("max_gram must be >= min_gram".to_string());
+        }
+        Ok(Self { min_gram, max_gram, edge_only, split_on_whitespace, prefix_marker, suffix_marker })
+    }
+
+    /// Generate character n-grams for `text`.
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+
+        if self.split_on_whitespace {
+            for unit in text.split_whitespace() {
+                self.ngrams_of(unit, &mut out);
+            }
+        } else {
+            self.ngrams_of(text.trim(), &mut out);
+        }
+
+        out
+    }
+
+    /// Append the n-grams of a single unit (word or whole string) to `out`, wrapping the unit in
+    /// the configured boundary markers first.
+    fn ngrams_of(&self, unit: &str, out: &mut Vec<String>) {
+        if unit.is_empty() {
+            return;
+        }
+
+        // Wrap with boundary markers if configured.
+        let wrapped = match (&self.prefix_marker, &self.suffix_marker) {
+            (None, None) => unit.to_string(),
+            (prefix, suffix) => {
+                let mut s = String::new();
+                if let Some(p) = prefix { s.push_str(p); }
+                s.push_str(unit);
+                if let Some(sfx) = suffix { s.push_str(sfx); }
+                s
+            }
+        };
+
+        let chars: Vec<char> = wrapped.chars().collect();
+        let n = chars.len();
+
+        if self.edge_only {
+            // Prefix n-grams: the first `len` characters for each length in range.
+            for len in self.min_gram..=self.max_gram {
+                if len > n {
+                    break;
+                }
+                out.push(chars[0..len].iter().collect());
+            }
+        } else {
+            // Sliding window of each length in range.
+            for len in self.min_gram..=self.max_gram {
+                if len > n {
+                    continue;
+                }
+                for start in 0..=(n - len) {
+                    out.push(chars[start..start + len].iter().collect());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cjk_bigrams() {
+        let tokenizer = NgramTokenizer::new(2, 2, false, true, None, None).unwrap();
+        let toks = tokenizer.tokenize("搜索引擎");
+        assert_eq!(toks, vec![
+            "搜索".to_string(),
+            "索引".to_string(),
+            "引擎".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_edge_only_prefix_grams() {
+        let tokenizer = NgramTokenizer::new(1, 3, true, true, None, None).unwrap();
+        let toks = tokenizer.tokenize("cat");
+        assert_eq!(toks, vec![
+            "c".to_string(),
+            "ca".to_string(),
+            "cat".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_boundary_markers() {
+        let tokenizer = NgramTokenizer::new(2, 2, false, true, Some("^".to_string()), Some("$".to_string())).unwrap();
+        let toks = tokenizer.tokenize("ab");
+        assert_eq!(toks, vec![
+            "^a".to_string(),
+            "ab".to_string(),
+            "b$".to_string(),
+        ]);
+    }
+}
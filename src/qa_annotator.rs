@@ -36,7 +36,17 @@ impl PyQAAnnotator {
         normalize: bool,
         normalization_from: String,
     ) -> PyResult<Self> {
-        let tokenizer = regex_tokenizer::PyRegexTokenizer::new(pattern, lowercase, normalize, normalization_from)?;
+        let tokenizer = regex_tokenizer::PyRegexTokenizer::new(
+            pattern,
+            lowercase,
+            normalize,
+            normalization_from,
+            std::collections::HashSet::new(),
+            None,
+            None,
+            None,
+            None,
+        )?;
         Ok(Self {docid_to_tokenized_corpus, tokenizer})
     }
 
@@ -67,7 +77,7 @@ impl PyQAAnnotator {
                 // Tokenize answers
                 let mut answers: Vec<Vec<String>> = Vec::new();
                 for answer in answer_texts {
-                    answers.push(self.tokenizer.inner.tokenize(answer.clone()));
+                    answers.push(self.tokenizer.inner.tokenize(answer.clone(), false));
                 }
                 
                 // Judge whether there is at least one answer in answers that is sub-strings of tokenized_corpus
@@ -97,15 +107,21 @@ impl PyQAAnnotator {
     /// ## Args:
     ///     qid_to_docids (HashMap<String, Vec<String>>): qid -> [doc_id]. All retrieval results
     ///     qid_to_answers (HashMap<String, Vec<String>>): qid -> [answer_str].
-    /// 
+    ///     max_edits (u32): Maximum per-token Levenshtein distance tolerated when matching an
+    ///                      answer token against a corpus token. `0` (default) keeps the exact
+    ///                      subsequence fast path; `1`/`2` absorb OCR noise and morphological drift.
+    ///
     /// ## Returns:
-    ///     qrels (HashMap<String, HashMap<String, u32>>): qid -> doc_id -> 1/0 (revelent/irrevelent) 
+    ///     qrels (HashMap<String, HashMap<String, u32>>): qid -> doc_id -> 1/0 (revelent/irrevelent)
+    #[pyo3(signature = (qid_to_docids, qid_to_answers, max_edits=0))]
     fn annotate(
         &self,
         py: Python,
         qid_to_docids: HashMap<String, Vec<String>>,    // qid -> [doc_id]. All retrieval results
         qid_to_answers: HashMap<String, Vec<String>>,   // qid -> [answer_str]
+        max_edits: u32,
     ) -> PyResult<HashMap<String, HashMap<String, u32>>> {       // Return: {qid -> pid -> has_answer}
+        let max_edits = max_edits as usize;
         py.allow_threads(|| {
             let qrels: HashMap<String, HashMap<String, u32>> = qid_to_docids
                 .into_par_iter()  // Parallel qid
@@ -116,7 +132,7 @@ impl PyQAAnnotator {
                         .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!("Missing answers for QID: {}", qid)))?;
                     let answers: Vec<Vec<String>> = answer_texts
                         .par_iter()
-                        .map(|answer| self.tokenizer.inner.tokenize(answer.clone()))
+                        .map(|answer| self.tokenizer.inner.tokenize(answer.clone(), false))
                         .collect();
 
                     // Judge whether there is at least one answer in answers that is sub-strings of tokenized_corpus
@@ -127,7 +143,12 @@ impl PyQAAnnotator {
                                 .docid_to_tokenized_corpus
                                 .get(&docid)
                                 .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyKeyError, _>(format!("Missing tokenized corpus for docid: {}", docid)))?;
-                            let has_answer = regex_tokenizer::is_subsequence_multi(&answers, tokenized_corpus);
+                            // Exact fast path when no edits are allowed; banded-Levenshtein fuzzy match otherwise.
+                            let has_answer = if max_edits == 0 {
+                                regex_tokenizer::is_subsequence_multi(&answers, tokenized_corpus)
+                            } else {
+                                regex_tokenizer::is_fuzzy_subsequence_multi(&answers, tokenized_corpus, max_edits)
+                            };
                             Ok((docid, has_answer as u32))
                         })
                         .collect::<PyResult<HashMap<String, u32>>>()?;
@@ -1,10 +1,12 @@
 use onig::Regex;
+use rust_stemmers::{Algorithm, Stemmer};
 use unicode_normalization::UnicodeNormalization;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 
-/// PyO3 wrapper of RegexTokenizer. 
+/// PyO3 wrapper of RegexTokenizer.
 /// RegexTokenizer minic the tokenization code from Facebook/DPR & DrQA codebase,
 /// performing a regex-based tokenization on the english string input.
 #[pyclass(name = "RegexTokenizer")]
@@ -16,52 +18,107 @@ pub struct PyRegexTokenizer {
 impl PyRegexTokenizer {
     #[new]
     #[pyo3(signature = (
-        pattern=r"(?im)([\p{L}\p{N}\p{M}]+)|([^\p{Z}\p{C}])".to_string(), 
-        lowercase=true, 
-        normalize=true, 
-        normalization_from="nfd".to_string()
+        pattern=r"(?im)([\p{L}\p{N}\p{M}]+)|([^\p{Z}\p{C}])".to_string(),
+        lowercase=true,
+        normalize=true,
+        normalization_from="nfd".to_string(),
+        stopwords=HashSet::new(),
+        stopword_file=None,
+        allowed_vocab=None,
+        allowed_vocab_file=None,
+        stemmer_lang=None
     ))]
     pub fn new(
-        pattern: String, 
+        pattern: String,
         lowercase: bool,
         normalize: bool,
         normalization_from: String,
+        stopwords: HashSet<String>,
+        stopword_file: Option<&str>,
+        allowed_vocab: Option<HashSet<String>>,
+        allowed_vocab_file: Option<&str>,
+        stemmer_lang: Option<&str>,
     ) -> PyResult<Self> {
         // Default Args
         // + Word Boundary Regex
         // + lowercase
         // + NFD normalize
-        let inner = RegexTokenizer::new(pattern, lowercase, normalize, normalization_from)
+        let filters = TokenFilters::resolve(stopwords, stopword_file, allowed_vocab, allowed_vocab_file)
+                                        .map_err(|err| PyValueError::new_err(err))?;
+        let stemmer = build_stemmer(stemmer_lang).map_err(|err| PyValueError::new_err(err))?;
+        let inner = RegexTokenizer::new(pattern, lowercase, normalize, normalization_from, filters, stemmer)
                                         .map_err(|err| PyValueError::new_err(err))?;
         Ok(Self { inner: inner })
     }
 
+    /// Build a tokenizer from an ordered list of typed steps, borrowing the
+    /// "sequence of normalizers / pre-tokenizers, any of which may be `None`"
+    /// design from HuggingFace tokenizers. Each step runs in declared order;
+    /// an empty list is the identity tokenizer (the whole string as one token).
+    ///
+    /// ### Args:
+    ///     steps (list[tuple[str, dict[str, str]]]): Ordered `(name, kwargs)` pairs. Supported names:
+    ///         `"normalize"`  kwargs `{"form": "nfd"|"nfc"|"nfkd"|"nfkc"}`
+    ///         `"lowercase"`  no kwargs
+    ///         `"strip_accents"`  no kwargs (drops `\p{M}` combining marks after NFD)
+    ///         `"replace"`  kwargs `{"pattern": <regex>, "replacement": <str>}`
+    ///         `"split"`  kwargs `{"pattern": <regex>}`
+    #[staticmethod]
+    #[pyo3(signature = (
+        steps,
+        stopwords=HashSet::new(),
+        stopword_file=None,
+        allowed_vocab=None,
+        allowed_vocab_file=None,
+        stemmer_lang=None
+    ))]
+    pub fn from_steps(
+        steps: Vec<(String, HashMap<String, String>)>,
+        stopwords: HashSet<String>,
+        stopword_file: Option<&str>,
+        allowed_vocab: Option<HashSet<String>>,
+        allowed_vocab_file: Option<&str>,
+        stemmer_lang: Option<&str>,
+    ) -> PyResult<Self> {
+        let filters = TokenFilters::resolve(stopwords, stopword_file, allowed_vocab, allowed_vocab_file)
+                                        .map_err(|err| PyValueError::new_err(err))?;
+        let stemmer = build_stemmer(stemmer_lang).map_err(|err| PyValueError::new_err(err))?;
+        let inner = RegexTokenizer::from_steps(steps, filters, stemmer).map_err(|err| PyValueError::new_err(err))?;
+        Ok(Self { inner })
+    }
+
+    #[pyo3(signature = (text, fold_diacritics = false))]
     pub fn tokenize(
         &self,
         py: Python,
         text: String,
+        fold_diacritics: bool,
     ) -> Vec<String> {
-        py.allow_threads(|| self.inner.tokenize(text))
+        py.allow_threads(|| self.inner.tokenize(text, fold_diacritics))
     }
 
+    #[pyo3(signature = (texts, fold_diacritics = false))]
     pub fn batch_tokenize(
         &self,
         py: Python,
         texts: Vec<String>,
+        fold_diacritics: bool,
     ) -> Vec<Vec<String>> {
         py.allow_threads(|| {
             texts.into_par_iter()
-                 .map(|text| self.inner.tokenize(text))
+                 .map(|text| self.inner.tokenize(text, fold_diacritics))
                  .collect()
         })
     }
-    
+
+    #[pyo3(signature = (texts, fold_diacritics = false))]
     pub fn __call__(
         &self,
         py: Python,
         texts: Vec<String>,
+        fold_diacritics: bool,
     ) -> Vec<Vec<String>> {
-        self.batch_tokenize(py, texts)
+        self.batch_tokenize(py, texts, fold_diacritics)
     }
 }
 
@@ -94,13 +151,269 @@ pub fn is_subsequence_multi(answers: &Vec<Vec<String>>, text: &Vec<String>) -> b
     false
 }
 
+/// Whether `a` and `b` are within `max_edits` Levenshtein distance of each other.
+///
+/// Only the diagonal band of width `2 * max_edits + 1` of the DP matrix is filled, giving
+/// `O(n * max_edits)` per comparison; anything outside the band already exceeds the budget.
+/// `max_edits == 0` short-circuits to exact equality.
+#[allow(unused)]
+pub fn within_edit_distance(a: &str, b: &str, max_edits: usize) -> bool {
+    if max_edits == 0 {
+        return a == b;
+    }
+
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let n = a.len();
+    let m = b.len();
+
+    // Length difference alone already blows the budget.
+    if (n as isize - m as isize).unsigned_abs() as usize > max_edits {
+        return false;
+    }
+
+    let k = max_edits;
+    let big = k + 1; // A saturating sentinel strictly greater than the budget.
+
+    // Row 0 of the banded matrix: edit distance from an empty prefix of `a`.
+    let mut prev: Vec<usize> = (0..=m).map(|j| if j <= k { j } else { big }).collect();
+    let mut cur: Vec<usize> = vec![big; m + 1];
+
+    for i in 1..=n {
+        let lo = if i > k { i - k } else { 0 };
+        let hi = (i + k).min(m);
+
+        // The two rows are reused, so only the cells just outside the band (read by this row
+        // and the next one) are reset to the sentinel.
+        if lo == 0 {
+            cur[0] = i; // Only reachable while the first column is still inside the band.
+        } else {
+            cur[lo - 1] = big;
+        }
+        if hi < m {
+            cur[hi + 1] = big;
+        }
+
+        for j in lo.max(1)..=hi {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            let mut best = prev[j - 1] + cost; // substitution / match (diagonal)
+            best = best.min(cur[j - 1] + 1); // insertion (left)
+            best = best.min(prev[j] + 1); // deletion (up)
+            cur[j] = best;
+        }
+
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[m] <= max_edits
+}
+
+/// Fuzzy variant of [`is_subsequence`]: `answer` matches when it aligns to some contiguous window
+/// of `text` with each position within `max_edits` Levenshtein distance. `max_edits == 0` reduces
+/// to exact subsequence matching.
+#[allow(unused)]
+pub fn is_fuzzy_subsequence(answer: &[String], text: &[String], max_edits: usize) -> bool {
+    if answer.is_empty() {
+        return true;
+    }
+    if answer.len() > text.len() {
+        return false;
+    }
+
+    for i in 0..=(text.len() - answer.len()) {
+        let matched = answer
+            .iter()
+            .zip(text[i..i + answer.len()].iter())
+            .all(|(a, t)| within_edit_distance(a, t, max_edits));
+        if matched {
+            return true;
+        }
+    }
+    false
+}
+
+/// Fuzzy variant of [`is_subsequence_multi`]: true when any `answer` fuzzy-matches `text`.
+#[allow(unused)]
+pub fn is_fuzzy_subsequence_multi(answers: &[Vec<String>], text: &[String], max_edits: usize) -> bool {
+    answers.iter().any(|answer| is_fuzzy_subsequence(answer, text, max_edits))
+}
+
+/// Build a Snowball/Porter stemmer for the given language code, or `None` for the explicit
+/// `no-stem` / empty passthrough. Accepts both ISO codes ("en") and full names ("english").
+pub(crate) fn build_stemmer(lang: Option<&str>) -> Result<Option<Stemmer>, String> {
+    let code = match lang {
+        None => return Ok(None),
+        Some(c) => c.to_lowercase(),
+    };
+    let algorithm = match code.as_str() {
+        "no-stem" | "none" | "" => return Ok(None),
+        "ar" | "arabic" => Algorithm::Arabic,
+        "da" | "danish" => Algorithm::Danish,
+        "nl" | "dutch" => Algorithm::Dutch,
+        "en" | "english" => Algorithm::English,
+        "fi" | "finnish" => Algorithm::Finnish,
+        "fr" | "french" => Algorithm::French,
+        "de" | "german" => Algorithm::German,
+        "el" | "greek" => Algorithm::Greek,
+        "hu" | "hungarian" => Algorithm::Hungarian,
+        "it" | "italian" => Algorithm::Italian,
+        "no" | "norwegian" => Algorithm::Norwegian,
+        "pt" | "portuguese" => Algorithm::Portuguese,
+        "ro" | "romanian" => Algorithm::Romanian,
+        "ru" | "russian" => Algorithm::Russian,
+        "es" | "spanish" => Algorithm::Spanish,
+        "sv" | "swedish" => Algorithm::Swedish,
+        "ta" | "tamil" => Algorithm::Tamil,
+        "tr" | "turkish" => Algorithm::Turkish,
+        other => return Err(format!("No Snowball stemmer for language `{}`", other)),
+    };
+    Ok(Some(Stemmer::create(algorithm)))
+}
+
+/// Whether `text` contains any CJK codepoint (Han, Hiragana, Katakana, Hangul and the common
+/// CJK symbol/punctuation blocks). Transliterating these scripts into pinyin/romaji destroys
+/// meaning, so diacritic folding skips any span that contains one.
+pub(crate) fn contains_cjk(text: &str) -> bool {
+    text.chars().any(|c| {
+        let cp = c as u32;
+        (0x3000..=0x303F).contains(&cp)     // CJK symbols & punctuation
+            || (0x3040..=0x309F).contains(&cp)  // Hiragana
+            || (0x30A0..=0x30FF).contains(&cp)  // Katakana
+            || (0x3400..=0x4DBF).contains(&cp)  // CJK Ext A
+            || (0x4E00..=0x9FFF).contains(&cp)  // CJK Unified Ideographs
+            || (0xF900..=0xFAFF).contains(&cp)  // CJK Compatibility Ideographs
+            || (0xAC00..=0xD7AF).contains(&cp)  // Hangul syllables
+            || (0x1100..=0x11FF).contains(&cp)  // Hangul Jamo
+            || (0x20000..=0x2A6DF).contains(&cp) // CJK Ext B
+    })
+}
+
+/// Push `token` onto `out`, additionally emitting its ASCII-folded transliteration when safe,
+/// following MeiliSearch's dual-token recipe: only when the token contains no CJK codepoint AND
+/// the deunicoded form actually differs from the original do we emit BOTH the original and the
+/// folded token (chained); otherwise only the original is emitted.
+pub(crate) fn push_with_optional_fold(out: &mut Vec<String>, token: String) {
+    if !contains_cjk(&token) {
+        let folded = deunicode::deunicode(&token);
+        if folded != token && !folded.is_empty() {
+            out.push(token);
+            out.push(folded);
+            return;
+        }
+    }
+    out.push(token);
+}
+
+/// Replace every match of `re` in `text` with the literal `replacement`.
+/// `onig::Regex` has no built-in `replace_all`, so we stitch the output from
+/// the match ranges ourselves.
+fn replace_all(re: &Regex, text: &str, replacement: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end) in re.find_iter(text) {
+        result.push_str(&text[last..start]);
+        result.push_str(replacement);
+        last = end;
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+/// Apply the unicode normalization `form` to `text`. `form` is assumed to be
+/// one of the four validated forms.
+fn normalize_form(text: &str, form: &str) -> String {
+    match form {
+        "nfd" => text.nfd().collect(),
+        "nfc" => text.nfc().collect(),
+        "nfkd" => text.nfkd().collect(),
+        "nfkc" => text.nfkc().collect(),
+        _ => text.to_string(),
+    }
+}
+
+/// A single normalizer / pre-tokenizer step. Normalizer steps map each current
+/// piece to a new piece; `Split` re-pieces every current piece via regex
+/// captures (mirroring the legacy single-regex path).
+enum NormalizerStep {
+    /// Unicode normalize each piece with the given form.
+    Normalize(String),
+    /// Lowercase each piece.
+    Lowercase,
+    /// Drop `\p{M}` combining marks after NFD decomposition (accent folding).
+    StripAccents(Regex),
+    /// Replace every regex match in each piece with `replacement`.
+    Replace { re: Regex, replacement: String },
+    /// Re-split each piece into the sequence of regex captures.
+    Split(Regex),
+}
+
+/// Post-tokenization token filters: drop stopwords and, optionally, restrict output to an
+/// allowed vocabulary. Both sets are compared against the final (post-normalize/lowercase)
+/// tokens and may be loaded from newline-delimited resource files.
+pub struct TokenFilters {
+    stopwords: HashSet<String>,
+    allowed_vocab: Option<HashSet<String>>,
+}
+
+impl TokenFilters {
+    /// Resolve the explicit sets together with any words loaded from resource files.
+    /// File-loaded words are unioned on top of the explicit sets.
+    pub fn resolve(
+        mut stopwords: HashSet<String>,
+        stopword_file: Option<&str>,
+        allowed_vocab: Option<HashSet<String>>,
+        allowed_vocab_file: Option<&str>,
+    ) -> Result<Self, String> {
+        if let Some(path) = stopword_file {
+            stopwords.extend(load_words_from_file(path)?);
+        }
+
+        let allowed_vocab = match (allowed_vocab, allowed_vocab_file) {
+            (base, Some(path)) => {
+                let mut set = base.unwrap_or_default();
+                set.extend(load_words_from_file(path)?);
+                Some(set)
+            }
+            (base, None) => base,
+        };
+
+        Ok(Self { stopwords, allowed_vocab })
+    }
+
+    /// Drop stopwords and out-of-vocabulary tokens in place.
+    fn apply(&self, tokens: &mut Vec<String>) {
+        if !self.stopwords.is_empty() {
+            tokens.retain(|t| !self.stopwords.contains(t));
+        }
+        if let Some(allowed) = &self.allowed_vocab {
+            tokens.retain(|t| allowed.contains(t));
+        }
+    }
+}
+
+/// Load a newline-delimited resource file into a set of non-empty, trimmed words.
+fn load_words_from_file(path: &str) -> Result<HashSet<String>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read `{}`: {}", path, e))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
 /// RegexTokenizer minic the tokenization code from Facebook/DPR & DrQA codebase,
 /// performing a regex-based tokenization on the english string input.
+///
+/// Internally the tokenizer is an ordered pipeline of [`NormalizerStep`]s; the
+/// legacy `new` / `new_default` constructors build the fixed
+/// normalize -> split -> lowercase pipeline, while `from_steps` exposes the
+/// full composable form. Token filters (stopwords / allowed vocabulary) run on
+/// the final token list.
 pub struct RegexTokenizer {
-    re: Regex,
-    lowercase: bool,
-    normalize: bool,
-    normalization_from: String,
+    steps: Vec<NormalizerStep>,
+    filters: TokenFilters,
+    stemmer: Option<Stemmer>,
 }
 
 #[allow(unused)]
@@ -108,90 +421,186 @@ impl RegexTokenizer {
     /// Args:
     ///     pattern (String): Regex pattern to cut word boundary.
     ///     lowercase (bool): Whether to lowercase inputs.
-    ///     normalize (bool): Whether to unicode normalize 
+    ///     normalize (bool): Whether to unicode normalize
     ///     normalization_from (String):Normalization form.
     pub fn new(
-        pattern: String, 
-        lowercase: bool, 
+        pattern: String,
+        lowercase: bool,
         normalize: bool,
         normalization_from: String,
+        filters: TokenFilters,
+        stemmer: Option<Stemmer>,
     ) -> Result<Self, String> {
-        let re = Regex::new(pattern.as_str()).map_err(|e| e.description().to_string())?;
         let normalization_from = normalization_from.to_lowercase();
         let valid_forms = vec!["nfd", "nfc", "nfkd", "nfkc"];
         if !valid_forms.contains(&normalization_from.as_str()) {
             return Err(format!("Invalid normalization_from {}", normalization_from).to_string())
         }
 
-        Ok(RegexTokenizer { re, lowercase, normalize, normalization_from })
+        // Rebuild the fixed legacy ordering as a step pipeline:
+        // optional normalize, then split on the word-boundary regex, then optional lowercase.
+        let mut steps: Vec<NormalizerStep> = Vec::new();
+        if normalize {
+            steps.push(NormalizerStep::Normalize(normalization_from));
+        }
+        let re = Regex::new(pattern.as_str()).map_err(|e| e.description().to_string())?;
+        steps.push(NormalizerStep::Split(re));
+        if lowercase {
+            steps.push(NormalizerStep::Lowercase);
+        }
+
+        Ok(RegexTokenizer { steps, filters, stemmer })
+    }
+
+    /// Build the tokenizer from an ordered list of `(name, kwargs)` steps.
+    /// See [`PyRegexTokenizer::from_steps`] for the supported step names.
+    pub fn from_steps(
+        steps: Vec<(String, HashMap<String, String>)>,
+        filters: TokenFilters,
+        stemmer: Option<Stemmer>,
+    ) -> Result<Self, String> {
+        let valid_forms = vec!["nfd", "nfc", "nfkd", "nfkc"];
+        let mut built: Vec<NormalizerStep> = Vec::with_capacity(steps.len());
+        for (name, kwargs) in steps {
+            match name.as_str() {
+                "normalize" => {
+                    let form = kwargs.get("form").map(|s| s.to_lowercase()).unwrap_or_else(|| "nfd".to_string());
+                    if !valid_forms.contains(&form.as_str()) {
+                        return Err(format!("Invalid normalization form {}", form));
+                    }
+                    built.push(NormalizerStep::Normalize(form));
+                }
+                "lowercase" => built.push(NormalizerStep::Lowercase),
+                "strip_accents" => {
+                    let re = Regex::new(r"\p{M}+").map_err(|e| e.description().to_string())?;
+                    built.push(NormalizerStep::StripAccents(re));
+                }
+                "replace" => {
+                    let pattern = kwargs.get("pattern")
+                        .ok_or_else(|| "Step `replace` requires a `pattern` kwarg".to_string())?;
+                    let replacement = kwargs.get("replacement").cloned().unwrap_or_default();
+                    let re = Regex::new(pattern.as_str()).map_err(|e| e.description().to_string())?;
+                    built.push(NormalizerStep::Replace { re, replacement });
+                }
+                "split" => {
+                    let pattern = kwargs.get("pattern")
+                        .ok_or_else(|| "Step `split` requires a `pattern` kwarg".to_string())?;
+                    let re = Regex::new(pattern.as_str()).map_err(|e| e.description().to_string())?;
+                    built.push(NormalizerStep::Split(re));
+                }
+                other => return Err(format!("Unknown tokenizer step `{}`", other)),
+            }
+        }
+        Ok(RegexTokenizer { steps: built, filters, stemmer })
     }
 
     /// Create a default regex tokenizer
-    /// 
+    ///
     /// Default Pattern:
-    ///     (?i): IGNORECASE.   
-    ///     (?m): MULTILINE.   
-    ///     r'[\p{L}\p{N}\p{M}]+': L - Letter; N - Number; M - Mark.   
-    ///     r'[^\p{Z}\p{C}]': Z - White Separator; C - Control.   
+    ///     (?i): IGNORECASE.
+    ///     (?m): MULTILINE.
+    ///     r'[\p{L}\p{N}\p{M}]+': L - Letter; N - Number; M - Mark.
+    ///     r'[^\p{Z}\p{C}]': Z - White Separator; C - Control.
     pub fn new_default() -> Result<Self, String> {
         let pattern = r"(?im)([\p{L}\p{N}\p{M}]+)|([^\p{Z}\p{C}])".to_string();
         let lowercase = true;
         let normalize = true;
         let normalization_from = "nfd".to_string();
-        Self::new(pattern, lowercase, normalize, normalization_from)
+        let filters = TokenFilters::resolve(HashSet::new(), None, None, None)?;
+        Self::new(pattern, lowercase, normalize, normalization_from, filters, None)
     }
 
-    /// Perform regex-based tokenization on `text`
-    pub fn tokenize(&self, mut text: String) -> Vec<String> {
-        if self.normalize {
-            if self.normalization_from == "nfd" {
-                text = text.nfd().collect();
-            } 
-            else if self.normalization_from == "nfc" {
-                text = text.nfc().collect();
-            }
-            else if self.normalization_from == "nfkd" {
-                text = text.nfkd().collect();
+    /// Perform regex-based tokenization on `text` by running the step pipeline.
+    ///
+    /// When `fold_diacritics` is set, every non-CJK token whose ASCII-folded transliteration
+    /// differs from the original is emitted alongside that folded form, so e.g. "café" and
+    /// "cafe" both land in the sparse rep.
+    pub fn tokenize(&self, text: String, fold_diacritics: bool) -> Vec<String> {
+        // The working set of pieces. Normalizer steps map each piece in place,
+        // while `Split` re-pieces every current piece via regex captures.
+        let mut pieces: Vec<String> = vec![text];
+
+        for step in &self.steps {
+            match step {
+                NormalizerStep::Normalize(form) => {
+                    for piece in pieces.iter_mut() {
+                        *piece = normalize_form(piece, form);
+                    }
+                }
+                NormalizerStep::Lowercase => {
+                    for piece in pieces.iter_mut() {
+                        *piece = piece.to_lowercase();
+                    }
+                }
+                NormalizerStep::StripAccents(re) => {
+                    for piece in pieces.iter_mut() {
+                        let decomposed: String = piece.nfd().collect();
+                        *piece = replace_all(re, &decomposed, "");
+                    }
+                }
+                NormalizerStep::Replace { re, replacement } => {
+                    for piece in pieces.iter_mut() {
+                        *piece = replace_all(re, piece, replacement);
+                    }
+                }
+                NormalizerStep::Split(re) => {
+                    let mut next: Vec<String> = Vec::new();
+                    for piece in &pieces {
+                        for cap in re.captures_iter(piece.as_str()) {
+                            if let Some(matched) = cap.at(0) {
+                                next.push(matched.to_string());
+                            }
+                        }
+                    }
+                    pieces = next;
+                }
             }
-            else if self.normalization_from == "nfkc" {
-                text = text.nfkc().collect();
+        }
+
+        // Drop stopwords / out-of-vocabulary tokens from the final token list.
+        self.filters.apply(&mut pieces);
+
+        // Reduce each token to its stem (after stopword removal) when a stemmer is configured.
+        if let Some(stemmer) = &self.stemmer {
+            for piece in pieces.iter_mut() {
+                *piece = stemmer.stem(piece).into_owned();
             }
         }
 
-        let mut matches: Vec<String> = Vec::new();
-        for cap in self.re.captures_iter(text.as_str()) {
-            if let Some(matched) = cap.at(0) {
-                if self.lowercase {
-                    matches.push(matched.to_string().to_lowercase());
-                } else {
-                    matches.push(matched.to_string());
-                }
+        // Optionally chain an ASCII-folded transliteration after each eligible token.
+        if fold_diacritics {
+            let mut folded: Vec<String> = Vec::with_capacity(pieces.len());
+            for token in pieces {
+                push_with_optional_fold(&mut folded, token);
             }
+            pieces = folded;
         }
-        matches
+
+        pieces
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
     #[test]
     fn test_word_tokenize() {
         let text = String::from("Hello, ä¸–ç•Œ! 123 ðŸ˜Š\nAnother Line!");
 
         // Word Tokenize
         let tokenizer = RegexTokenizer::new_default().unwrap();
-        let text_toks = tokenizer.tokenize(text);
+        let text_toks = tokenizer.tokenize(text, false);
 
         assert_eq!(text_toks, vec![
-            "hello".to_string(), 
+            "hello".to_string(),
             ",".to_string(),
-            "ä¸–ç•Œ".to_string(), 
-            "!".to_string(), 
-            "123".to_string(), 
-            "ðŸ˜Š".to_string(), 
-            "another".to_string(), 
-            "line".to_string(), 
+            "ä¸–ç•Œ".to_string(),
+            "!".to_string(),
+            "123".to_string(),
+            "ðŸ˜Š".to_string(),
+            "another".to_string(),
+            "line".to_string(),
             "!".to_string()
         ]);
     }
@@ -206,11 +615,11 @@ mod tests {
 
         // Word Tokenize
         let tokenizer = RegexTokenizer::new_default().unwrap();
-        let text_toks = tokenizer.tokenize(text);
-        let ans1_toks = tokenizer.tokenize(ans1);
-        let ans2_toks = tokenizer.tokenize(ans2);
-        let ans3_toks = tokenizer.tokenize(ans3);
-        let ans4_toks = tokenizer.tokenize(ans4);
+        let text_toks = tokenizer.tokenize(text, false);
+        let ans1_toks = tokenizer.tokenize(ans1, false);
+        let ans2_toks = tokenizer.tokenize(ans2, false);
+        let ans3_toks = tokenizer.tokenize(ans3, false);
+        let ans4_toks = tokenizer.tokenize(ans4, false);
 
         // [Test1] is_subsequence
         assert_eq!(is_subsequence(&ans1_toks, &text_toks), false);
@@ -229,15 +638,75 @@ mod tests {
 
         // Word Tokenize
         let tokenizer = RegexTokenizer::new_default().unwrap();
-        let text_toks = tokenizer.tokenize(text);
-        let ans1_toks = tokenizer.tokenize(ans1);
-        let ans2_toks = tokenizer.tokenize(ans2);
-        let ans3_toks = tokenizer.tokenize(ans3);
-        let ans4_toks = tokenizer.tokenize(ans4);
+        let text_toks = tokenizer.tokenize(text, false);
+        let ans1_toks = tokenizer.tokenize(ans1, false);
+        let ans2_toks = tokenizer.tokenize(ans2, false);
+        let ans3_toks = tokenizer.tokenize(ans3, false);
+        let ans4_toks = tokenizer.tokenize(ans4, false);
 
         // [Test2] is_subsequence_multi
         let ans_toks_vec = vec![ans1_toks, ans2_toks, ans3_toks, ans4_toks];
         assert_eq!(is_subsequence_multi(&ans_toks_vec, &text_toks), true);
     }
-}
 
+    #[test]
+    fn test_from_steps_strip_accents_without_lowercase() {
+        // DPR-style accent folding that keeps the original casing.
+        let tokenizer = RegexTokenizer::from_steps(vec![
+            ("strip_accents".to_string(), HashMap::new()),
+            ("split".to_string(), {
+                let mut kw = HashMap::new();
+                kw.insert("pattern".to_string(), r"([\p{L}\p{N}]+)".to_string());
+                kw
+            }),
+        ], TokenFilters::resolve(HashSet::new(), None, None, None).unwrap(), None).unwrap();
+
+        let toks = tokenizer.tokenize(String::from("Café Crème"), false);
+        assert_eq!(toks, vec!["Cafe".to_string(), "Creme".to_string()]);
+    }
+
+    #[test]
+    fn test_from_steps_replace_then_split() {
+        // Two composed regexes: punctuation replacement followed by a split.
+        let tokenizer = RegexTokenizer::from_steps(vec![
+            ("replace".to_string(), {
+                let mut kw = HashMap::new();
+                kw.insert("pattern".to_string(), r"[-_]".to_string());
+                kw.insert("replacement".to_string(), " ".to_string());
+                kw
+            }),
+            ("lowercase".to_string(), HashMap::new()),
+            ("split".to_string(), {
+                let mut kw = HashMap::new();
+                kw.insert("pattern".to_string(), r"([\p{L}\p{N}]+)".to_string());
+                kw
+            }),
+        ], TokenFilters::resolve(HashSet::new(), None, None, None).unwrap(), None).unwrap();
+
+        let toks = tokenizer.tokenize(String::from("Faster-Than_Light"), false);
+        assert_eq!(toks, vec!["faster".to_string(), "than".to_string(), "light".to_string()]);
+    }
+
+    #[test]
+    fn test_stopword_and_vocab_filtering() {
+        // Stopwords are dropped and only allowed-vocabulary terms survive.
+        let mut stopwords = HashSet::new();
+        stopwords.insert("the".to_string());
+        let mut allowed = HashSet::new();
+        allowed.insert("quick".to_string());
+        allowed.insert("fox".to_string());
+
+        let filters = TokenFilters::resolve(stopwords, None, Some(allowed), None).unwrap();
+        let tokenizer = RegexTokenizer::new(
+            r"(?im)([\p{L}\p{N}\p{M}]+)|([^\p{Z}\p{C}])".to_string(),
+            true,
+            true,
+            "nfd".to_string(),
+            filters,
+            None,
+        ).unwrap();
+
+        let toks = tokenizer.tokenize(String::from("the quick brown fox"), false);
+        assert_eq!(toks, vec!["quick".to_string(), "fox".to_string()]);
+    }
+}